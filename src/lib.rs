@@ -44,6 +44,7 @@
 #![deny(missing_doc_code_examples)]
 
 use std::borrow::Borrow;
+use std::io::{self, Read};
 
 /// Return a `Sized` object as a byte slice.  😬
 ///
@@ -58,6 +59,43 @@ pub fn asbuf<T: Sized>(buf: &T) -> &[u8] {
   }
 }
 
+/// Per-byte rendering format for the hex dump's byte column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+  /// Lower-case hexadecimal, e.g. `"4a"`.
+  LowerHex,
+
+  /// Upper-case hexadecimal, e.g. `"4A"`.
+  UpperHex,
+
+  /// Octal, e.g. `"112"`.
+  Octal,
+
+  /// Binary, e.g. `"01001010"`.
+  Binary
+}
+
+impl Format {
+  /// Width, in characters, of a single rendered byte cell.
+  fn width(self) -> usize {
+    match self {
+      Format::LowerHex | Format::UpperHex => 2,
+      Format::Octal => 3,
+      Format::Binary => 8
+    }
+  }
+
+  /// Render a single byte according to this format.
+  fn render(self, byte: u8) -> String {
+    match self {
+      Format::LowerHex => format!("{:02x}", byte),
+      Format::UpperHex => format!("{:02X}", byte),
+      Format::Octal => format!("{:03o}", byte),
+      Format::Binary => format!("{:08b}", byte)
+    }
+  }
+}
+
 /// Hex dumper configuration context.
 pub struct Config {
   /// Number of columns in hex dump.  Defaults to 16.
@@ -66,12 +104,33 @@ pub struct Config {
   /// A base offset.  Defaults to 0.  If it's useful to display the addresses
   /// of a dumped buffer, this can be set to the initial address of the
   /// buffer.
-  pub offs: usize
+  pub offs: usize,
+
+  /// Rendering format for each byte in the byte column.  Defaults to
+  /// [`Format::LowerHex`].
+  pub format: Format,
+
+  /// Insert an extra space after every `group` bytes in the byte column,
+  /// mirroring the `xxd`/`hexdump -C` gutter between byte clusters.  `0`
+  /// (the default) disables grouping.
+  pub group: usize,
+
+  /// Collapse consecutive lines with identical byte content into a single
+  /// `*` sentinel line, the way GNU `od`/`hexdump` do.  Defaults to
+  /// `false`.  The final line is always emitted, even if it is identical to
+  /// the one before it, so the tail offset stays visible.
+  pub squeeze: bool
 }
 
 impl Default for Config {
   fn default() -> Self {
-    Config { cols: 16, offs: 0 }
+    Config {
+      cols: 16,
+      offs: 0,
+      format: Format::LowerHex,
+      group: 0,
+      squeeze: false
+    }
   }
 }
 
@@ -117,53 +176,479 @@ where
 ///   println!("{:08x} {} {}", offs, hex, ascii);
 /// });
 /// ```
+///
+/// # Example: grouped hex column
+/// With `group` set, an extra space splits the hex column into clusters,
+/// padded out to line up even on a short final block.
+/// ```
+/// use std::cell::RefCell;
+/// use dbgtools_hexdump::{Config, hexdump_buf};
+///
+/// let data: &[u8] = &[1, 2, 3, 4, 5];
+/// let lines = RefCell::new(Vec::new());
+///
+/// hexdump_buf(
+///   Config { cols: 8, group: 4, ..Default::default() },
+///   data,
+///   |offs, hex, ascii| {
+///     lines.borrow_mut().push((offs, hex.to_string(), ascii.to_string()));
+///   }
+/// );
+///
+/// assert_eq!(lines.borrow()[0].1, "01 02 03 04  05         ");
+/// ```
+///
+/// # Example: squeezing repeated lines
+/// With `squeeze` set, a run of identical lines collapses to a single `*`
+/// marker line, but the final line is always emitted so the tail offset
+/// stays visible.
+/// ```
+/// use std::cell::RefCell;
+/// use dbgtools_hexdump::{Config, hexdump_buf};
+///
+/// let data = [0u8; 32];
+/// let lines = RefCell::new(Vec::new());
+///
+/// hexdump_buf(
+///   Config { cols: 8, squeeze: true, ..Default::default() },
+///   &data,
+///   |offs, hex, ascii| {
+///     lines.borrow_mut().push((offs, hex.to_string(), ascii.to_string()));
+///   }
+/// );
+///
+/// let lines = lines.into_inner();
+/// assert_eq!(lines.len(), 3);
+/// assert_eq!(lines[0].1, "00 00 00 00 00 00 00 00");
+/// assert_eq!(lines[1], (8, "".to_string(), "*".to_string()));
+/// assert_eq!(lines[2].0, 24);
+/// ```
+///
+/// # Example: per-format cell width
+/// The byte cell width (and the padding used for a short final block)
+/// tracks the selected [`Format`] -- here `Format::Binary` renders each
+/// byte as 8 characters wide instead of the default 2.
+/// ```
+/// use std::cell::RefCell;
+/// use dbgtools_hexdump::{Config, Format, hexdump_buf};
+///
+/// let data: &[u8] = &[1, 2, 3];
+/// let lines = RefCell::new(Vec::new());
+///
+/// hexdump_buf(
+///   Config { cols: 4, format: Format::Binary, ..Default::default() },
+///   data,
+///   |offs, hex, ascii| {
+///     lines.borrow_mut().push((offs, hex.to_string(), ascii.to_string()));
+///   }
+/// );
+///
+/// assert_eq!(
+///   lines.borrow()[0].1,
+///   "00000001 00000010 00000011         "
+/// );
+/// ```
 pub fn hexdump_buf<C, F>(cfg: C, buf: &[u8], f: F)
 where
   C: Borrow<Config>,
   F: Fn(usize, &str, &str)
+{
+  for (offs, hex, ascii) in hexdump_iter(cfg, buf) {
+    f(offs, &hex, &ascii);
+  }
+}
+
+/// Iterator over hex dump lines, yielding `(offset, hex, ascii)` tuples.
+///
+/// The concrete type is not exposed publicly; [`hexdump_iter`] returns it
+/// as an opaque `impl Iterator`.
+struct HexdumpIter<'a, C> {
+  cfg: C,
+  buf: &'a [u8],
+  pos: usize,
+  offset: usize,
+  prev: Option<&'a [u8]>,
+  squeezing: bool
+}
+
+impl<'a, C: Borrow<Config>> HexdumpIter<'a, C> {
+  /// Advance the iterator, returning the raw block bytes alongside the
+  /// formatted hex/ASCII columns.  This is the shared core behind both the
+  /// public `(offset, hex, ascii)` [`Iterator`] impl and [`hexdump_styled`],
+  /// so squeeze/grouping/format handling only has to live in one place.
+  fn next_with_block(&mut self) -> Option<(usize, &'a [u8], String, String)> {
+    let cfg = self.cfg.borrow();
+
+    if cfg.cols == 0 {
+      // derpy caller
+      return None;
+    }
+
+    loop {
+      if self.pos >= self.buf.len() {
+        return None;
+      }
+
+      let end = (self.pos + cfg.cols).min(self.buf.len());
+      let block = &self.buf[self.pos..end];
+      let is_last = end >= self.buf.len();
+
+      let this_offs = self.offset;
+      self.pos = end;
+      self.offset += block.len();
+
+      if cfg.squeeze && !is_last && self.prev == Some(block) {
+        if !self.squeezing {
+          self.squeezing = true;
+          return Some((this_offs, block, String::new(), "*".to_string()));
+        }
+
+        continue;
+      }
+
+      self.squeezing = false;
+      self.prev = Some(block);
+
+      let (hex_str, ascii) = format_line(cfg, block);
+
+      return Some((this_offs, block, hex_str, ascii));
+    }
+  }
+}
+
+impl<'a, C: Borrow<Config>> Iterator for HexdumpIter<'a, C> {
+  type Item = (usize, String, String);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next_with_block().map(|(offs, _block, hex, ascii)| (offs, hex, ascii))
+  }
+}
+
+/// Generate a hex dump of a byte buffer (`&[u8]`) as an iterator of
+/// `(offset, hex, ascii)` lines, instead of driving a closure.
+///
+/// This allows dumps to be composed with the usual [`Iterator`]
+/// combinators -- mapped, collected, zipped, or fed straight into a
+/// `writeln!` loop -- rather than forcing imperative side effects in a
+/// callback.  [`hexdump`] and [`hexdump_buf`] are implemented on top of
+/// this.
+///
+/// ```
+/// use dbgtools_hexdump::{Config, hexdump_iter};
+///
+/// let data: &[u8] = &[1, 2, 3, 4];
+///
+/// let lines: Vec<String> = hexdump_iter(Config::default(), data)
+///   .map(|(offs, hex, ascii)| format!("{:08x} {} {}", offs, hex, ascii))
+///   .collect();
+/// ```
+pub fn hexdump_iter<'a, C>(
+  cfg: C,
+  buf: &'a [u8]
+) -> impl Iterator<Item = (usize, String, String)> + 'a
+where
+  C: Borrow<Config> + 'a
+{
+  make_iter(cfg, buf)
+}
+
+/// Build the shared [`HexdumpIter`] core.  Internal helper so
+/// [`hexdump_styled`] can drive [`HexdumpIter::next_with_block`] directly,
+/// while [`hexdump_iter`] only exposes the plain `(offset, hex, ascii)`
+/// [`Iterator`] to callers.
+fn make_iter<C>(cfg: C, buf: &[u8]) -> HexdumpIter<'_, C>
+where
+  C: Borrow<Config>
+{
+  let offset = cfg.borrow().offs;
+
+  HexdumpIter { cfg, buf, pos: 0, offset, prev: None, squeezing: false }
+}
+
+/// Generate a hex dump of a stream of bytes read incrementally from an
+/// [`io::Read`] source, and call a closure to process each hex dump line.
+///
+/// Unlike [`hexdump_buf`], this does not require the entire payload to be
+/// materialized in memory up front, which makes it suitable for large
+/// files, sockets, or other unbounded readers.  The source is read in
+/// `cfg.cols`-sized chunks; a short final chunk is right-padded the same
+/// way as in [`hexdump_buf`].  `cfg.squeeze` is honored too, using a
+/// one-block lookahead to tell the final line apart from a run of
+/// duplicates without buffering the whole stream.
+///
+/// ```
+/// use dbgtools_hexdump::{Config, hexdump_read};
+///
+/// let data: &[u8] = &[1, 2, 3, 4];
+///
+/// hexdump_read(Config::default(), data, |offs, hex, ascii| {
+///   println!("{:08x} {} {}", offs, hex, ascii);
+/// }).unwrap();
+/// ```
+///
+/// # Example: short reads don't prematurely pad
+/// A reader that only ever returns a single byte per `read()` call must
+/// still produce exactly the same lines as [`hexdump_buf`] on the same
+/// data -- the accumulation buffer only flushes a short, padded block on
+/// actual EOF, never on a short read.
+/// ```
+/// use std::cell::RefCell;
+/// use std::io::Read;
+/// use dbgtools_hexdump::{Config, hexdump_buf, hexdump_read};
+///
+/// struct OneByteAtATime<'a>(&'a [u8]);
+///
+/// impl<'a> Read for OneByteAtATime<'a> {
+///   fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+///     if self.0.is_empty() || buf.is_empty() {
+///       return Ok(0);
+///     }
+///     buf[0] = self.0[0];
+///     self.0 = &self.0[1..];
+///     Ok(1)
+///   }
+/// }
+///
+/// let data: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9];
+/// let cfg = Config { cols: 4, ..Default::default() };
+///
+/// let buffered = RefCell::new(Vec::new());
+/// hexdump_buf(&cfg, data, |offs, hex, ascii| {
+///   buffered.borrow_mut().push((offs, hex.to_string(), ascii.to_string()));
+/// });
+///
+/// let streamed = RefCell::new(Vec::new());
+/// hexdump_read(&cfg, OneByteAtATime(data), |offs, hex, ascii| {
+///   streamed.borrow_mut().push((offs, hex.to_string(), ascii.to_string()));
+/// }).unwrap();
+///
+/// assert_eq!(buffered.into_inner(), streamed.into_inner());
+/// ```
+///
+/// # Example: squeeze is honored while streaming
+/// ```
+/// use std::cell::RefCell;
+/// use dbgtools_hexdump::{Config, hexdump_read};
+///
+/// let data = [0u8; 32];
+/// let lines = RefCell::new(Vec::new());
+///
+/// hexdump_read(
+///   Config { cols: 8, squeeze: true, ..Default::default() },
+///   &data[..],
+///   |offs, hex, ascii| {
+///     lines.borrow_mut().push((offs, hex.to_string(), ascii.to_string()));
+///   }
+/// ).unwrap();
+///
+/// let lines = lines.into_inner();
+/// assert_eq!(lines.len(), 3);
+/// assert_eq!(lines[1], (8, "".to_string(), "*".to_string()));
+/// assert_eq!(lines[2].0, 24);
+/// ```
+pub fn hexdump_read<C, R, F>(cfg: C, mut reader: R, f: F) -> io::Result<()>
+where
+  C: Borrow<Config>,
+  R: Read,
+  F: Fn(usize, &str, &str)
 {
   let cfg = cfg.borrow();
 
   if cfg.cols == 0 {
     // derpy caller
-    return;
+    return Ok(());
   }
 
+  // Read one `cfg.cols`-sized (or, at EOF, shorter) block.  Returns `None`
+  // only once the reader has nothing left at all, so a short read that
+  // isn't EOF never produces a premature, padded block.
+  let read_block = |reader: &mut R| -> io::Result<Option<Vec<u8>>> {
+    let mut block = vec![0u8; cfg.cols];
+    let mut filled = 0;
+
+    loop {
+      match reader.read(&mut block[filled..])? {
+        0 => break,
+        n => {
+          filled += n;
+
+          if filled == cfg.cols {
+            break;
+          }
+        }
+      }
+    }
+
+    if filled == 0 {
+      Ok(None)
+    } else {
+      block.truncate(filled);
+      Ok(Some(block))
+    }
+  };
+
   let mut offset = cfg.offs;
 
-  let mut ascii = String::new();
+  // A one-block lookahead is kept so that, under `cfg.squeeze`, a block
+  // can be compared against its successor to tell whether it's the final
+  // line (which is always emitted) without buffering the whole stream.
+  let mut current = read_block(&mut reader)?;
+  let mut prev: Option<Vec<u8>> = None;
+  let mut squeezing = false;
 
-  for block in buf.chunks(cfg.cols) {
+  while let Some(block) = current {
     let this_offs = offset;
+    offset += block.len();
 
-    ascii.clear();
+    let next = read_block(&mut reader)?;
+    let is_last = next.is_none();
 
-    let mut vals = Vec::new();
-    for byte in block {
-      vals.push(format!("{:02x}", byte));
-
-      if *byte < 0x20 || *byte > 0x7e {
-        ascii.push('.');
-      } else {
-        ascii.push(char::from(*byte));
+    if cfg.squeeze && !is_last && prev.as_deref() == Some(block.as_slice()) {
+      if !squeezing {
+        squeezing = true;
+        f(this_offs, "", "*");
       }
+    } else {
+      squeezing = false;
+
+      let (hex_str, ascii) = format_line(cfg, &block);
+      f(this_offs, &hex_str, &ascii);
 
-      offset += 1;
+      prev = Some(block);
     }
 
-    let rem = cfg.cols - vals.len();
-    if rem > 0 {
-      let rest_it = std::iter::repeat("  ".to_string()).take(rem);
-      vals.extend(rest_it);
+    current = next;
+  }
+
+  Ok(())
+}
 
-      let rest_ascii = String::from(" ").repeat(rem);
-      ascii.push_str(&rest_ascii);
+/// Generate a hex dump of a byte buffer (`&[u8]`) and call a closure with
+/// both the formatted columns and the raw block bytes, so the application
+/// can re-render the line with its own styling (e.g. ANSI colors) based on
+/// [`classify`] -- null bytes, control bytes, and printable ASCII dimmed,
+/// colored, or highlighted differently.
+///
+/// This keeps the crate free of a color dependency while leaving the
+/// existing [`hexdump`], [`hexdump_buf`] and [`hexdump_iter`] untouched for
+/// callers that don't need per-byte styling.  It is built on the same
+/// [`HexdumpIter`] core as [`hexdump_buf`], so `cfg.squeeze`, `cfg.group`
+/// and `cfg.format` all apply exactly as they do there.
+///
+/// ```
+/// use dbgtools_hexdump::{classify, ByteKind, Config, hexdump_styled};
+///
+/// let data: &[u8] = &[0, 65, 1, 2];
+///
+/// hexdump_styled(Config::default(), data, |offs, raw, hex, ascii| {
+///   for byte in raw {
+///     match classify(*byte) {
+///       ByteKind::Null => print!("(null)"),
+///       ByteKind::Printable => print!("(printable)"),
+///       ByteKind::Control => print!("(control)")
+///     }
+///   }
+///   println!(" {:08x} {} {}", offs, hex, ascii);
+/// });
+/// ```
+pub fn hexdump_styled<C, F>(cfg: C, buf: &[u8], f: F)
+where
+  C: Borrow<Config>,
+  F: Fn(usize, &[u8], &str, &str)
+{
+  let mut iter = make_iter(cfg, buf);
+
+  while let Some((this_offs, block, hex_str, ascii)) = iter.next_with_block() {
+    f(this_offs, block, &hex_str, &ascii);
+  }
+}
+
+/// Classification of a byte's printability, useful for styling individual
+/// bytes (e.g. coloring) without the crate hardcoding any particular
+/// scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteKind {
+  /// The null byte (`0x00`).
+  Null,
+
+  /// A printable ASCII byte (`0x20..=0x7e`).
+  Printable,
+
+  /// Any other non-printable / control byte.
+  Control
+}
+
+/// Classify a single byte for styling purposes.
+///
+/// ```
+/// use dbgtools_hexdump::{classify, ByteKind};
+///
+/// assert_eq!(classify(0x00), ByteKind::Null);
+/// assert_eq!(classify(b'A'), ByteKind::Printable);
+/// assert_eq!(classify(0x01), ByteKind::Control);
+/// ```
+pub fn classify(byte: u8) -> ByteKind {
+  match byte {
+    0x00 => ByteKind::Null,
+    0x20..=0x7e => ByteKind::Printable,
+    _ => ByteKind::Control
+  }
+}
+
+/// Format a single block of at most `cfg.cols` bytes into its hex and ASCII
+/// columns, right-padding both to the full column width if the block is
+/// short.
+fn format_line(cfg: &Config, block: &[u8]) -> (String, String) {
+  let mut ascii = String::new();
+
+  let mut vals = Vec::new();
+  for byte in block {
+    vals.push(cfg.format.render(*byte));
+
+    match classify(*byte) {
+      ByteKind::Printable => ascii.push(char::from(*byte)),
+      ByteKind::Null | ByteKind::Control => ascii.push('.')
     }
+  }
+
+  let rem = cfg.cols - vals.len();
+  if rem > 0 {
+    let pad = " ".repeat(cfg.format.width());
+    let rest_it = std::iter::repeat(pad).take(rem);
+    vals.extend(rest_it);
+
+    let rest_ascii = String::from(" ").repeat(rem);
+    ascii.push_str(&rest_ascii);
+  }
 
-    let hex_str = vals.join(" ");
+  let hex_str = join_hex(&vals, cfg.group);
 
-    f(this_offs, &hex_str, &ascii);
+  (hex_str, ascii)
+}
+
+/// Join rendered byte cells into the hex column, inserting an extra space
+/// after every `group` cells (if `group` is non-zero) to form the
+/// `xxd`/`hexdump -C` style gutter.
+fn join_hex(vals: &[String], group: usize) -> String {
+  if group == 0 {
+    return vals.join(" ");
+  }
+
+  let mut hex_str = String::new();
+
+  for (i, val) in vals.iter().enumerate() {
+    if i > 0 {
+      hex_str.push(' ');
+
+      if i % group == 0 {
+        hex_str.push(' ');
+      }
+    }
+
+    hex_str.push_str(val);
   }
+
+  hex_str
 }
 
 // vim: set ft=rust et sw=2 ts=2 sts=2 cinoptions=2 tw=79 :